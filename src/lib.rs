@@ -5,6 +5,21 @@
 //! toggled state forever.
 //! It supports fuses with custom initial boolean state, as well as atomic fuses.
 //!
+//! [`FuseCell`]/[`AtomicFuseCell`] generalize this to arbitrary payloads: instead
+//! of toggling a boolean, they permanently switch their active value from an
+//! `initial` value to a `zapped` one, modeling one-way state transitions beyond
+//! booleans (e.g. a config permanently flipping from `Mode::Enabled` to
+//! `Mode::Disabled`).
+//!
+//! `AtomicFuse` defaults to `Acquire` loads and `AcqRel` zaps, with
+//! `_with`/`_with_ordering` methods to pick weaker orderings on hot paths, and
+//! `zap_with`/`zap_once_with` variants that run a callback exactly once, on the
+//! transition that actually zaps the fuse. `AtomicFuseCell` always uses `SeqCst`
+//! and has no ordering or callback variants.
+//!
+//! This crate only relies on `core`, and builds under `#![no_std]` when the
+//! default `std` feature is disabled (`default-features = false`).
+//!
 //! ## Example
 //!
 //! ```rust
@@ -22,16 +37,30 @@
 //! let already_zapped = fuse.zap_once();
 //! assert_eq!(already_zapped, Err(efuse::AlreadyZappedError));
 //! ```
+//!
+//! ```rust
+//! let mut cell = efuse::FuseCell::new("enabled", "disabled");
+//! assert_eq!(*cell.get(), "enabled");
+//!
+//! let zapped = cell.zap();
+//! assert_eq!(*zapped, "disabled");
+//! assert_eq!(*cell.get(), "disabled");
+//! ```
 
+#![cfg_attr(not(feature = "std"), no_std)]
 #![deny(missing_debug_implementations)]
 #![deny(missing_docs)]
 #![allow(clippy::trivially_copy_pass_by_ref)]
-#![allow(clippy::derive_hash_xor_eq)]
+#![allow(clippy::derived_hash_with_manual_eq)]
+#![allow(clippy::bool_assert_comparison)]
+#![allow(clippy::useless_conversion)]
+#![allow(clippy::from_over_into)]
 
-use std::hash::{Hash, Hasher};
-use std::ops::Not;
-use std::sync::atomic::AtomicBool;
-use std::sync::atomic::Ordering::SeqCst;
+use core::hash::{Hash, Hasher};
+use core::ops::Not;
+use core::sync::atomic::AtomicBool;
+use core::sync::atomic::Ordering;
+use core::sync::atomic::Ordering::{AcqRel, Acquire, SeqCst};
 
 /// Attempted to `zap_once` an already zapped fuse.
 #[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
@@ -48,7 +77,10 @@ pub struct Fuse {
 
 impl Fuse {
     /// Return a new fuse with the given initial state.
-    pub fn new(initial_state: bool) -> Self {
+    ///
+    /// This is a `const fn`, so it can be used to initialize `static`/`const`
+    /// items, e.g. `static FUSE: Fuse = Fuse::new(false);`.
+    pub const fn new(initial_state: bool) -> Self {
         Self {
             initial_state,
             zapped: false,
@@ -85,6 +117,32 @@ impl Fuse {
         Ok(self.zap())
     }
 
+    /// Zap this fuse (unconditionally), toggling its value permanently.
+    ///
+    /// `f` runs exactly once, only on the transition that actually flips
+    /// the fuse; it is not called on redundant re-zaps.
+    /// It returns the new value of this fuse.
+    pub fn zap_with<F: FnOnce()>(&mut self, f: F) -> bool {
+        if !self.zapped {
+            f();
+        }
+        self.zap()
+    }
+
+    /// Zap this fuse (conditionally), toggling its value permanently.
+    ///
+    /// `f` runs exactly once, only if this call is the one that flips the
+    /// fuse. If the fuse was already previously zapped, `f` is dropped
+    /// without being called and this returns an
+    /// [`AlreadyZappedError`](struct.AlreadyZappedError.html) error.
+    /// Otherwise, it returns the new value of this fuse.
+    pub fn zap_once_with<F: FnOnce()>(&mut self, f: F) -> Result<bool, AlreadyZappedError> {
+        if self.zapped {
+            return Err(AlreadyZappedError);
+        }
+        Ok(self.zap_with(f))
+    }
+
     /// Whether this fuse has already been zapped.
     pub fn is_zapped(&self) -> bool {
         self.zapped
@@ -131,8 +189,24 @@ pub struct AtomicFuse {
 }
 
 impl AtomicFuse {
+    /// An un-zapped fuse with `false` as the initial state, for use in
+    /// `const`/`static` position, e.g. `static FUSE: AtomicFuse = AtomicFuse::NEW_FALSE;`.
+    ///
+    /// Despite the interior mutability of `AtomicFuse`, using this constant
+    /// directly in a `static` item is sound: each `static` using it gets its
+    /// own evaluation of the `const`, so there is no shared state to
+    /// accidentally alias. Prefer `AtomicFuse::new(false)` instead of this
+    /// constant anywhere else (e.g. as a local variable or struct field
+    /// default), since copying it there would silently create independent
+    /// fuses rather than referencing shared state.
+    #[allow(clippy::declare_interior_mutable_const)]
+    pub const NEW_FALSE: AtomicFuse = AtomicFuse::new(false);
+
     /// Return a new fuse with the given initial state.
-    pub fn new(initial_state: bool) -> Self {
+    ///
+    /// This is a `const fn`, so it can be used to initialize `static`/`const`
+    /// items, e.g. `static FUSE: AtomicFuse = AtomicFuse::new(false);`.
+    pub const fn new(initial_state: bool) -> Self {
         Self {
             initial_state,
             zapped: AtomicBool::new(false),
@@ -144,34 +218,137 @@ impl AtomicFuse {
         self.initial_state
     }
 
+    /// Return current fuse value as a boolean, loading with the given
+    /// memory ordering.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `order` is [`Release`](core::sync::atomic::Ordering::Release)
+    /// or [`AcqRel`](core::sync::atomic::Ordering::AcqRel), as the underlying
+    /// load does not support them; see [`AtomicBool::load`](core::sync::atomic::AtomicBool::load).
+    pub fn as_bool_with(&self, order: Ordering) -> bool {
+        self.initial_state ^ self.zapped.load(order)
+    }
+
     /// Return current fuse value as a boolean.
+    ///
+    /// This loads with `Acquire` ordering; use
+    /// [`as_bool_with`](#method.as_bool_with) to pick a weaker ordering
+    /// (e.g. `Relaxed`) on hot paths that only need eventual visibility.
     pub fn as_bool(&self) -> bool {
-        self.initial_state ^ self.zapped.load(SeqCst)
+        self.as_bool_with(Acquire)
+    }
+
+    /// Zap this fuse (unconditionally), toggling its value permanently,
+    /// storing with the given memory ordering.
+    ///
+    /// It returns the new value of this fuse.
+    pub fn zap_with_ordering(&self, order: Ordering) -> bool {
+        self.zapped.fetch_or(true, order);
+        self.initial_state ^ true
     }
 
     /// Zap this fuse (unconditionally), toggling its value permanently.
     ///
+    /// This stores with `AcqRel` ordering; use
+    /// [`zap_with_ordering`](#method.zap_with_ordering) to pick a weaker
+    /// ordering on hot paths.
     /// It returns the new value of this fuse.
     pub fn zap(&self) -> bool {
-        self.zapped.fetch_or(true, SeqCst);
-        self.initial_state ^ true
+        self.zap_with_ordering(AcqRel)
+    }
+
+    /// Zap this fuse (conditionally), toggling its value permanently, using
+    /// the given success/failure memory orderings for the underlying
+    /// `compare_exchange`.
+    ///
+    /// If the fuse was already previously zapped, it returns an
+    /// [`AlreadyZappedError`](struct.AlreadyZappedError.html) error.
+    /// Otherwise, it returns the new value of this fuse.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `failure` is [`Release`](core::sync::atomic::Ordering::Release)
+    /// or [`AcqRel`](core::sync::atomic::Ordering::AcqRel), as the underlying
+    /// `compare_exchange` does not support them; see
+    /// [`AtomicBool::compare_exchange`](core::sync::atomic::AtomicBool::compare_exchange).
+    pub fn zap_once_with_ordering(
+        &self,
+        success: Ordering,
+        failure: Ordering,
+    ) -> Result<bool, AlreadyZappedError> {
+        if self
+            .zapped
+            .compare_exchange(false, true, success, failure)
+            .is_err()
+        {
+            return Err(AlreadyZappedError);
+        }
+        Ok(self.initial_state ^ true)
     }
 
     /// Zap this fuse (conditionally), toggling its value permanently.
     ///
+    /// This uses `AcqRel`/`Acquire` orderings; use
+    /// [`zap_once_with_ordering`](#method.zap_once_with_ordering) to pick
+    /// weaker orderings.
     /// If the fuse was already previously zapped, it returns an
     /// [`AlreadyZappedError`](struct.AlreadyZappedError.html) error.
     /// Otherwise, it returns the new value of this fuse.
     pub fn zap_once(&self) -> Result<bool, AlreadyZappedError> {
-        if self.zapped.compare_and_swap(false, true, SeqCst) {
+        self.zap_once_with_ordering(AcqRel, Acquire)
+    }
+
+    /// Zap this fuse (unconditionally), toggling its value permanently.
+    ///
+    /// `f` runs exactly once, only for the thread that wins the race to
+    /// flip the fuse; it is not called on redundant re-zaps.
+    /// It returns the new value of this fuse.
+    pub fn zap_with<F: FnOnce()>(&self, f: F) -> bool {
+        match self.zap_once_with(f) {
+            Ok(new_value) => new_value,
+            Err(AlreadyZappedError) => self.initial_state ^ true,
+        }
+    }
+
+    /// Zap this fuse (conditionally), toggling its value permanently.
+    ///
+    /// `f` runs exactly once, only for the thread that wins the
+    /// `compare_exchange` race to flip the fuse. If the fuse was already
+    /// previously zapped, `f` is dropped without being called and this
+    /// returns an [`AlreadyZappedError`](struct.AlreadyZappedError.html)
+    /// error. Otherwise, it returns the new value of this fuse.
+    pub fn zap_once_with<F: FnOnce()>(&self, f: F) -> Result<bool, AlreadyZappedError> {
+        if self
+            .zapped
+            .compare_exchange(false, true, AcqRel, Acquire)
+            .is_err()
+        {
             return Err(AlreadyZappedError);
         }
+        f();
         Ok(self.initial_state ^ true)
     }
 
+    /// Whether this fuse has already been zapped, loading with the given
+    /// memory ordering.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `order` is [`Release`](core::sync::atomic::Ordering::Release)
+    /// or [`AcqRel`](core::sync::atomic::Ordering::AcqRel), as the underlying
+    /// load does not support them; see [`AtomicBool::load`](core::sync::atomic::AtomicBool::load).
+    pub fn is_zapped_with(&self, order: Ordering) -> bool {
+        self.zapped.load(order)
+    }
+
     /// Whether this fuse has already been zapped.
+    ///
+    /// This loads with `Acquire` ordering; use
+    /// [`is_zapped_with`](#method.is_zapped_with) to pick a weaker ordering
+    /// on hot paths.
     pub fn is_zapped(&self) -> bool {
-        self.zapped.load(SeqCst)
+        self.is_zapped_with(Acquire)
     }
 }
 
@@ -192,7 +369,7 @@ impl Into<bool> for AtomicFuse {
 
 impl Clone for AtomicFuse {
     fn clone(&self) -> Self {
-        let zapped = self.zapped.load(SeqCst);
+        let zapped = self.is_zapped();
         Self {
             initial_state: self.initial_state,
             zapped: AtomicBool::new(zapped),
@@ -222,9 +399,172 @@ impl Not for AtomicFuse {
     }
 }
 
+/// Software fuse carrying a payload, with custom initial and zapped values.
+///
+/// Unlike [`Fuse`](struct.Fuse.html), which only toggles a boolean, a
+/// `FuseCell<T>` permanently switches its active value from `initial` to
+/// `zapped` once zapped. This lets fuses model one-way state transitions
+/// beyond booleans (e.g. a config permanently flipping from
+/// `Mode::Enabled` to `Mode::Disabled`).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FuseCell<T> {
+    initial: T,
+    alternate: T,
+    zapped: bool,
+}
+
+impl<T> FuseCell<T> {
+    /// Return a new fuse cell with the given initial and zapped values.
+    ///
+    /// This is a `const fn`, so it can be used to initialize `static`/`const`
+    /// items.
+    pub const fn new(initial: T, zapped: T) -> Self {
+        Self {
+            initial,
+            alternate: zapped,
+            zapped: false,
+        }
+    }
+
+    /// Return a reference to the currently active value.
+    pub fn get(&self) -> &T {
+        if self.is_zapped() {
+            &self.alternate
+        } else {
+            &self.initial
+        }
+    }
+
+    /// Zap this cell (unconditionally), permanently switching the active value.
+    ///
+    /// It returns a reference to the new active value.
+    pub fn zap(&mut self) -> &T {
+        self.zapped = true;
+        &self.alternate
+    }
+
+    /// Zap this cell (conditionally), permanently switching the active value.
+    ///
+    /// If the cell was already previously zapped, it returns an
+    /// [`AlreadyZappedError`](struct.AlreadyZappedError.html) error.
+    /// Otherwise, it returns a reference to the new active value.
+    pub fn zap_once(&mut self) -> Result<&T, AlreadyZappedError> {
+        if self.zapped {
+            return Err(AlreadyZappedError);
+        }
+        Ok(self.zap())
+    }
+
+    /// Whether this cell has already been zapped.
+    pub fn is_zapped(&self) -> bool {
+        self.zapped
+    }
+}
+
+impl<T: Hash> Hash for FuseCell<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.initial.hash(state);
+        self.alternate.hash(state);
+        self.is_zapped().hash(state);
+    }
+}
+
+/// Atomic software fuse carrying a payload, with custom initial and zapped values.
+///
+/// Unlike [`AtomicFuse`](struct.AtomicFuse.html), which only toggles a
+/// boolean, an `AtomicFuseCell<T>` permanently switches its active value
+/// from `initial` to `zapped` once zapped, guarding the switch with an
+/// `AtomicBool`.
+#[derive(Debug)]
+pub struct AtomicFuseCell<T> {
+    initial: T,
+    alternate: T,
+    zapped: AtomicBool,
+}
+
+impl<T> AtomicFuseCell<T> {
+    /// Return a new fuse cell with the given initial and zapped values.
+    ///
+    /// This is a `const fn`, so it can be used to initialize `static`/`const`
+    /// items.
+    pub const fn new(initial: T, zapped: T) -> Self {
+        Self {
+            initial,
+            alternate: zapped,
+            zapped: AtomicBool::new(false),
+        }
+    }
+
+    /// Return a reference to the currently active value.
+    pub fn get(&self) -> &T {
+        if self.is_zapped() {
+            &self.alternate
+        } else {
+            &self.initial
+        }
+    }
+
+    /// Zap this cell (unconditionally), permanently switching the active value.
+    ///
+    /// It returns a reference to the new active value.
+    pub fn zap(&self) -> &T {
+        self.zapped.fetch_or(true, SeqCst);
+        &self.alternate
+    }
+
+    /// Zap this cell (conditionally), permanently switching the active value.
+    ///
+    /// If the cell was already previously zapped, it returns an
+    /// [`AlreadyZappedError`](struct.AlreadyZappedError.html) error.
+    /// Otherwise, it returns a reference to the new active value.
+    pub fn zap_once(&self) -> Result<&T, AlreadyZappedError> {
+        if self
+            .zapped
+            .compare_exchange(false, true, SeqCst, SeqCst)
+            .is_err()
+        {
+            return Err(AlreadyZappedError);
+        }
+        Ok(&self.alternate)
+    }
+
+    /// Whether this cell has already been zapped.
+    pub fn is_zapped(&self) -> bool {
+        self.zapped.load(SeqCst)
+    }
+}
+
+impl<T: Clone> Clone for AtomicFuseCell<T> {
+    fn clone(&self) -> Self {
+        Self {
+            initial: self.initial.clone(),
+            alternate: self.alternate.clone(),
+            zapped: AtomicBool::new(self.is_zapped()),
+        }
+    }
+}
+
+impl<T: PartialEq> PartialEq for AtomicFuseCell<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.is_zapped() == other.is_zapped()
+            && self.initial == other.initial
+            && self.alternate == other.alternate
+    }
+}
+impl<T: Eq> Eq for AtomicFuseCell<T> {}
+
+impl<T: Hash> Hash for AtomicFuseCell<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.initial.hash(state);
+        self.alternate.hash(state);
+        self.is_zapped().hash(state);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use core::sync::atomic::AtomicUsize;
 
     #[test]
     fn test_defaults() {
@@ -245,7 +585,7 @@ mod tests {
 
     #[test]
     fn test_zaps() {
-        for init in vec![false, true] {
+        for init in [false, true] {
             {
                 let mut fuse = Fuse::new(init);
                 assert_eq!(fuse.as_bool(), init);
@@ -278,6 +618,126 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_const_new() {
+        static FUSE: Fuse = Fuse::new(false);
+        static AFUSE: AtomicFuse = AtomicFuse::new(false);
+        static AFUSE_DEFAULT: AtomicFuse = AtomicFuse::NEW_FALSE;
+        const CELL: FuseCell<bool> = FuseCell::new(false, true);
+
+        assert_eq!(FUSE.as_bool(), false);
+        assert_eq!(AFUSE.as_bool(), false);
+        assert_eq!(AFUSE_DEFAULT.as_bool(), false);
+        assert_eq!(*CELL.get(), false);
+    }
+
+    #[test]
+    fn test_cell_zaps() {
+        {
+            let mut cell = FuseCell::new("enabled", "disabled");
+            assert_eq!(*cell.get(), "enabled");
+            let new1 = *cell.zap_once().unwrap();
+            assert_eq!(new1, "disabled");
+            assert_eq!(*cell.get(), "disabled");
+            assert_eq!(cell.is_zapped(), true);
+            let err = cell.zap_once().unwrap_err();
+            assert_eq!(err, AlreadyZappedError);
+            assert_eq!(*cell.get(), "disabled");
+            let new2 = *cell.zap();
+            assert_eq!(new2, "disabled");
+        }
+
+        {
+            let acell = AtomicFuseCell::new("enabled", "disabled");
+            assert_eq!(*acell.get(), "enabled");
+            let new1 = *acell.zap_once().unwrap();
+            assert_eq!(new1, "disabled");
+            assert_eq!(*acell.get(), "disabled");
+            assert_eq!(acell.is_zapped(), true);
+            let err = acell.zap_once().unwrap_err();
+            assert_eq!(err, AlreadyZappedError);
+            assert_eq!(*acell.get(), "disabled");
+            let new2 = *acell.zap();
+            assert_eq!(new2, "disabled");
+        }
+    }
+
+    #[test]
+    fn test_orderings() {
+        let afuse = AtomicFuse::new(false);
+        assert_eq!(afuse.as_bool_with(Ordering::Relaxed), false);
+        assert_eq!(afuse.is_zapped_with(Ordering::Relaxed), false);
+        let new1 = afuse.zap_once_with_ordering(Ordering::Relaxed, Ordering::Relaxed);
+        assert_eq!(new1, Ok(true));
+        assert_eq!(afuse.as_bool_with(Ordering::Relaxed), true);
+        let err = afuse.zap_once_with_ordering(Ordering::Relaxed, Ordering::Relaxed);
+        assert_eq!(err, Err(AlreadyZappedError));
+        let new2 = afuse.zap_with_ordering(Ordering::Relaxed);
+        assert_eq!(new2, true);
+    }
+
+    #[test]
+    #[should_panic(expected = "there is no such thing as a release load")]
+    fn test_as_bool_with_release_panics() {
+        let afuse = AtomicFuse::new(false);
+        let _ = afuse.as_bool_with(Ordering::Release);
+    }
+
+    #[test]
+    #[should_panic(expected = "there is no such thing as a release load")]
+    fn test_is_zapped_with_release_panics() {
+        let afuse = AtomicFuse::new(false);
+        let _ = afuse.is_zapped_with(Ordering::Release);
+    }
+
+    #[test]
+    #[should_panic(expected = "there is no such thing as a release failure ordering")]
+    fn test_zap_once_with_ordering_release_failure_panics() {
+        let afuse = AtomicFuse::new(false);
+        let _ = afuse.zap_once_with_ordering(Ordering::SeqCst, Ordering::Release);
+    }
+
+    #[test]
+    fn test_zap_with() {
+        {
+            let mut fuse = Fuse::new(false);
+            let mut calls = 0;
+            let new1 = fuse.zap_once_with(|| calls += 1).unwrap();
+            assert_eq!(new1, true);
+            assert_eq!(calls, 1);
+            let err = fuse.zap_once_with(|| calls += 1).unwrap_err();
+            assert_eq!(err, AlreadyZappedError);
+            assert_eq!(calls, 1);
+            let new2 = fuse.zap_with(|| calls += 1);
+            assert_eq!(new2, true);
+            assert_eq!(calls, 1);
+        }
+
+        {
+            let afuse = AtomicFuse::new(false);
+            let calls = AtomicUsize::new(0);
+            let new1 = afuse
+                .zap_once_with(|| {
+                    calls.fetch_add(1, SeqCst);
+                })
+                .unwrap();
+            assert_eq!(new1, true);
+            assert_eq!(calls.load(SeqCst), 1);
+            let err = afuse
+                .zap_once_with(|| {
+                    calls.fetch_add(1, SeqCst);
+                })
+                .unwrap_err();
+            assert_eq!(err, AlreadyZappedError);
+            assert_eq!(calls.load(SeqCst), 1);
+            let new2 = afuse.zap_with(|| {
+                calls.fetch_add(1, SeqCst);
+            });
+            assert_eq!(new2, true);
+            assert_eq!(calls.load(SeqCst), 1);
+        }
+    }
+
     #[test]
     fn test_ops() {
         {